@@ -1,509 +1,1117 @@
-// Note: a lot of this file is copypasta from zkcrypto/bellman
-
-use std::ops::{AddAssign, MulAssign, SubAssign};
-
-use crate::polynomial::Polynomial;
-use crate::KZGError;
-use blstrs::Scalar;
-use pairing::group::ff::Field;
-use pairing::group::ff::PrimeField;
-
-#[cfg(feature = "parallel")]
-use crate::utils::chunk_by_num_threads;
-#[cfg(feature = "parallel")]
-use crate::utils::log2;
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct EvaluationDomain {
-    pub(crate) coeffs: Vec<Scalar>,
-    pub(crate) d: usize,
-    pub(crate) exp: u32,
-    pub(crate) omega: Scalar,
-    pub(crate) omegainv: Scalar,
-    pub(crate) geninv: Scalar,
-    pub(crate) minv: Scalar,
-}
-
-impl From<EvaluationDomain> for Polynomial {
-    fn from(domain: EvaluationDomain) -> Polynomial {
-        Polynomial::new(domain.coeffs)
-    }
-}
-
-impl AsRef<[Scalar]> for EvaluationDomain {
-    fn as_ref(&self) -> &[Scalar] {
-        &self.coeffs
-    }
-}
-
-impl AsMut<[Scalar]> for EvaluationDomain {
-    fn as_mut(&mut self) -> &mut [Scalar] {
-        &mut self.coeffs
-    }
-}
-
-impl EvaluationDomain {
-    pub fn into_coeffs(self) -> Vec<Scalar> {
-        self.coeffs
-    }
-
-    pub fn len(&self) -> usize {
-        self.coeffs.len()
-    }
-
-    // returns m, exp, and omega
-    pub fn compute_omega(d: usize) -> Result<(usize, u32, Scalar), KZGError> {
-        // Compute the size of our evaluation domain
-        let mut m = 1;
-        let mut exp = 0;
-
-        // TODO cache this in a lazy static
-        while m < d {
-            m *= 2;
-            exp += 1;
-
-            // The pairing-friendly curve may not be able to support
-            // large enough (radix2) evaluation domains.
-            if exp >= Scalar::S {
-                return Err(KZGError::PolynomialDegreeTooLarge);
-            }
-        }
-
-        // Compute omega, the 2^exp primitive root of unity
-        let omega = Scalar::root_of_unity().pow_vartime(&[1 << (Scalar::S - exp)]);
-
-        Ok((m, exp, omega))
-    }
-
-    pub fn clone_with_different_coeffs(&self, coeffs: Vec<Scalar>) -> EvaluationDomain {
-        EvaluationDomain { coeffs, ..*self }
-    }
-
-    pub fn new(coeffs: Vec<Scalar>, d: usize, exp: u32, omega: Scalar) -> Self {
-        EvaluationDomain {
-            coeffs,
-            d,
-            exp,
-            omega,
-            omegainv: omega.invert().unwrap(),
-            geninv: Scalar::multiplicative_generator().invert().unwrap(),
-            minv: Scalar::from(d as u64).invert().unwrap(),
-        }
-    }
-
-    pub fn from_coeffs(mut coeffs: Vec<Scalar>) -> Result<EvaluationDomain, KZGError> {
-        let (m, exp, omega) = Self::compute_omega(coeffs.len())?;
-
-        // Extend the coeffs vector with zeroes if necessary
-        coeffs.resize(m, Scalar::zero());
-
-        Ok(EvaluationDomain {
-            d: m,
-            coeffs,
-            exp,
-            omega,
-            omegainv: omega.invert().unwrap(),
-            geninv: Scalar::multiplicative_generator().invert().unwrap(),
-            minv: Scalar::from(m as u64).invert().unwrap(),
-        })
-    }
-
-    pub fn fft(&mut self) {
-        best_fft(&mut self.coeffs, &self.omega, self.exp);
-    }
-
-    pub fn ifft(&mut self) {
-        best_fft(&mut self.coeffs, &self.omegainv, self.exp);
-
-        #[cfg(feature = "parallel")]
-        rayon::scope(|scope| {
-            let minv = self.minv;
-
-            let chunk_size = chunk_by_num_threads(self.coeffs.len());
-
-            for v in self.coeffs.chunks_mut(chunk_size) {
-                scope.spawn(move |_scope| {
-                    for v in v {
-                        v.mul_assign(&minv);
-                    }
-                });
-            }
-        });
-
-        #[cfg(not(feature = "parallel"))]
-        {
-            let minv = self.minv;
-            for v in self.coeffs.iter_mut() {
-                v.mul_assign(&minv);
-            }
-        }
-    }
-
-    pub fn distribute_powers(&mut self, g: Scalar) {
-        #[cfg(feature = "parallel")]
-        rayon::scope(|scope| {
-            let chunk_size = chunk_by_num_threads(self.coeffs.len());
-
-            for (i, v) in self.coeffs.chunks_mut(chunk_size).enumerate() {
-                scope.spawn(move |_scope| {
-                    let mut u = g.pow_vartime(&[(i * chunk_size) as u64]);
-                    for v in v.iter_mut() {
-                        v.mul_assign(&u);
-                        u.mul_assign(&g);
-                    }
-                });
-            }
-        });
-
-        #[cfg(not(feature = "parallel"))]
-        {
-            for (i, v) in self.coeffs.iter_mut().enumerate() {
-                let mut u = g.pow_vartime(&[i as u64]);
-                v.mul_assign(&u);
-                u.mul_assign(&g);
-            }
-        };
-    }
-
-    pub fn coset_fft(&mut self) {
-        self.distribute_powers(Scalar::multiplicative_generator());
-        self.fft();
-    }
-
-    pub fn icoset_fft(&mut self) {
-        let geninv = self.geninv;
-
-        self.ifft();
-        self.distribute_powers(geninv);
-    }
-
-    /// This evaluates t(tau) for this domain, which is
-    /// tau^m - 1 for these radix-2 domains.
-    pub fn z(&self, tau: &Scalar) -> Scalar {
-        let mut tmp = tau.pow_vartime(&[self.coeffs.len() as u64]);
-        tmp.sub_assign(&Scalar::one());
-
-        tmp
-    }
-
-    /// The target polynomial is the zero polynomial in our
-    /// evaluation domain, so we must perform division over
-    /// a coset.
-    pub fn divide_by_z_on_coset(&mut self) {
-        let i = self
-            .z(&Scalar::multiplicative_generator())
-            .invert()
-            .unwrap();
-
-        #[cfg(feature = "parallel")]
-        rayon::scope(|scope| {
-            let chunk_size = chunk_by_num_threads(self.coeffs.len());
-
-            for v in self.coeffs.chunks_mut(chunk_size) {
-                scope.spawn(move |_scope| {
-                    for v in v {
-                        v.mul_assign(&i);
-                    }
-                });
-            }
-        });
-
-        #[cfg(not(feature = "parallel"))]
-        {
-            for v in self.coeffs.iter_mut() {
-                v.mul_assign(&i);
-            }
-        }
-    }
-
-    /// Perform O(n) multiplication of two polynomials in the domain.
-    pub fn mul_assign(&mut self, other: &EvaluationDomain) {
-        assert_eq!(self.coeffs.len(), other.coeffs.len());
-
-        #[cfg(feature = "parallel")]
-        rayon::scope(|scope| {
-            let chunk_size = chunk_by_num_threads(self.coeffs.len());
-
-            for (a, b) in self
-                .coeffs
-                .chunks_mut(chunk_size)
-                .zip(other.coeffs.chunks(chunk_size))
-            {
-                scope.spawn(move |_scope| {
-                    for (a, b) in a.iter_mut().zip(b.iter()) {
-                        a.mul_assign(b);
-                    }
-                });
-            }
-        });
-
-        #[cfg(not(feature = "parallel"))]
-        for (a, b) in self.coeffs.iter_mut().zip(other.coeffs.iter()) {
-            a.mul_assign(b);
-        }
-    }
-
-    /// Perform O(n) subtraction of one polynomial from another in the domain.
-    pub fn sub_assign(&mut self, other: &EvaluationDomain) {
-        assert_eq!(self.coeffs.len(), other.coeffs.len());
-
-        #[cfg(feature = "parallel")]
-        rayon::scope(|scope| {
-            let chunk_size = chunk_by_num_threads(self.coeffs.len());
-
-            for (a, b) in self
-                .coeffs
-                .chunks_mut(chunk_size)
-                .zip(other.coeffs.chunks(chunk_size))
-            {
-                scope.spawn(move |_scope| {
-                    for (a, b) in a.iter_mut().zip(b.iter()) {
-                        a.sub_assign(b);
-                    }
-                });
-            }
-        });
-
-        #[cfg(not(feature = "parallel"))]
-        for (a, b) in self.coeffs.iter_mut().zip(other.coeffs.iter()) {
-            a.sub_assign(b);
-        }
-    }
-}
-
-fn best_fft(a: &mut [Scalar], omega: &Scalar, log_n: u32) {
-    #[cfg(feature = "parallel")]
-    {
-        let log_cpus = log2(rayon::current_num_threads() as u64) as u32;
-
-        if log_n <= log_cpus {
-            serial_fft(a, omega, log_n);
-        } else {
-            parallel_fft(a, omega, log_n, log_cpus);
-        }
-    }
-
-    #[cfg(not(feature = "parallel"))]
-    serial_fft(a, omega, log_n);
-}
-
-#[allow(clippy::many_single_char_names)]
-fn serial_fft(a: &mut [Scalar], omega: &Scalar, log_n: u32) {
-    fn bitreverse(mut n: u32, l: u32) -> u32 {
-        let mut r = 0;
-        for _ in 0..l {
-            r = (r << 1) | (n & 1);
-            n >>= 1;
-        }
-        r
-    }
-
-    let n = a.len() as u32;
-    assert_eq!(n, 1 << log_n);
-
-    for k in 0..n {
-        let rk = bitreverse(k, log_n);
-        if k < rk {
-            a.swap(rk as usize, k as usize);
-        }
-    }
-
-    let mut m = 1;
-    for _ in 0..log_n {
-        let w_m = omega.pow_vartime(&[u64::from(n / (2 * m))]);
-
-        let mut k = 0;
-        while k < n {
-            let mut w = Scalar::one();
-            for j in 0..m {
-                let mut t = a[(k + j + m) as usize];
-                t.mul_assign(&w);
-                let mut tmp = a[(k + j) as usize];
-                tmp.sub_assign(&t);
-                a[(k + j + m) as usize] = tmp;
-                a[(k + j) as usize].add_assign(&t);
-                w.mul_assign(&w_m);
-            }
-
-            k += 2 * m;
-        }
-
-        m *= 2;
-    }
-}
-
-#[cfg(feature = "parallel")]
-fn parallel_fft(a: &mut [Scalar], omega: &Scalar, log_n: u32, log_cpus: u32) {
-    assert!(log_n >= log_cpus);
-
-    let num_cpus = 1 << log_cpus;
-    let log_new_n = log_n - log_cpus;
-    let mut tmp = vec![vec![Scalar::zero(); 1 << log_new_n]; num_cpus];
-    let new_omega = omega.pow_vartime(&[num_cpus as u64]);
-
-    rayon::scope(|scope| {
-        let a = &*a;
-
-        for (j, tmp) in tmp.iter_mut().enumerate() {
-            scope.spawn(move |_scope| {
-                // Shuffle into a sub-FFT
-                let omega_j = omega.pow_vartime(&[j as u64]);
-                let omega_step = omega.pow_vartime(&[(j as u64) << log_new_n]);
-
-                let mut elt = Scalar::one();
-                for (i, tmp) in tmp.iter_mut().enumerate() {
-                    for s in 0..num_cpus {
-                        let idx = (i + (s << log_new_n)) % (1 << log_n);
-                        let mut t = a[idx];
-                        t.mul_assign(&elt);
-                        tmp.add_assign(&t);
-                        elt.mul_assign(&omega_step);
-                    }
-                    elt.mul_assign(&omega_j);
-                }
-
-                // Perform sub-FFT
-                serial_fft(tmp, &new_omega, log_new_n);
-            });
-        }
-    });
-
-    // TODO: does this hurt or help?
-    rayon::scope(|scope| {
-        let chunk_size = chunk_by_num_threads(a.len());
-        let tmp = &tmp;
-
-        for (idx, a) in a.chunks_mut(chunk_size).enumerate() {
-            scope.spawn(move |_scope| {
-                let mut idx = idx * chunk_size;
-                let mask = (1 << log_cpus) - 1;
-                for a in a {
-                    *a = tmp[idx & mask][idx >> log_cpus];
-                    idx += 1;
-                }
-            });
-        }
-    });
-}
-
-#[cfg(all(feature = "serde_support", feature = "b12_381"))]
-use crate::wrapper_types::SerializablePrimeField;
-
-#[cfg(all(feature = "serde_support", feature = "b12_381"))]
-use bls12_381::Scalar;
-
-#[cfg(all(feature = "serde_support", feature = "b12_381"))]
-#[derive(Serialize, Deserialize)]
-pub struct SerializableEvaluationDomain {
-    coeffs: Vec<SerializablePrimeField<Scalar>>,
-    exp: u32,
-    omega: SerializablePrimeField<Scalar>,
-    omegainv: SerializablePrimeField<Scalar>,
-    geninv: SerializablePrimeField<Scalar>,
-    minv: SerializablePrimeField<Scalar>,
-}
-
-#[cfg(test)]
-use rand::{rngs::SmallRng, Rng, SeedableRng};
-
-// Test multiplying various (low degree) polynomials together and
-// comparing with naive evaluations.
-#[test]
-fn polynomial_arith() {
-    fn test_mul<R: Rng>(mut rng: &mut R) {
-        for coeffs_a in vec![1, 5, 10, 50] {
-            for coeffs_b in vec![1, 5, 10, 50] {
-                let a: Vec<_> = (0..coeffs_a).map(|_| Scalar::random(&mut rng)).collect();
-                let b: Vec<_> = (0..coeffs_b).map(|_| Scalar::random(&mut rng)).collect();
-
-                let a = Polynomial::new_from_coeffs(a, coeffs_a - 1);
-                let b = Polynomial::new_from_coeffs(b, coeffs_b - 1);
-
-                // naive evaluation
-                let naive = a.clone() * b.clone();
-                let fft = a.fft_mul(&b);
-
-                assert!(naive == fft);
-            }
-        }
-    }
-
-    let rng = &mut SmallRng::from_seed([42; 32]);
-
-    test_mul(rng);
-}
-
-#[cfg(test)]
-fn random_evals(rng: &mut SmallRng, d: usize) -> EvaluationDomain {
-    let mut coeffs = vec![Scalar::zero(); d];
-
-    for i in 0..d {
-        coeffs[i] = rng.gen::<u64>().into();
-    }
-
-    EvaluationDomain::from_coeffs(coeffs).unwrap()
-}
-
-#[test]
-fn fft_composition() {
-    use rand::RngCore;
-
-    fn test_comp<R: RngCore>(mut rng: &mut R) {
-        for coeffs in 0..10 {
-            let coeffs = 1 << coeffs;
-
-            let mut v = vec![];
-            for _ in 0..coeffs {
-                v.push(Scalar::random(&mut rng));
-            }
-
-            let mut domain = EvaluationDomain::from_coeffs(v.clone()).unwrap();
-            domain.ifft();
-            domain.fft();
-            assert!(v == domain.coeffs);
-            domain.fft();
-            domain.ifft();
-            assert!(v == domain.coeffs);
-            domain.icoset_fft();
-            domain.coset_fft();
-            assert!(v == domain.coeffs);
-            domain.coset_fft();
-            domain.icoset_fft();
-            assert!(v == domain.coeffs);
-        }
-    }
-
-    let rng = &mut rand::thread_rng();
-
-    test_comp(rng);
-}
-
-#[cfg(feature = "parallel")]
-#[test]
-fn parallel_fft_consistency() {
-    use rand::RngCore;
-    use std::cmp::min;
-
-    fn test_consistency<R: RngCore>(mut rng: &mut R) {
-        for _ in 0..5 {
-            for log_d in 0..10 {
-                let d = 1 << log_d;
-
-                let v1 = (0..d).map(|_| Scalar::random(&mut rng)).collect::<Vec<_>>();
-                let mut v1 = EvaluationDomain::from_coeffs(v1).unwrap();
-                let mut v2 = EvaluationDomain::from_coeffs(v1.coeffs.clone()).unwrap();
-
-                for log_cpus in log_d..min(log_d + 1, 3) {
-                    parallel_fft(&mut v1.coeffs, &v1.omega, log_d, log_cpus);
-                    serial_fft(&mut v2.coeffs, &v2.omega, log_d);
-
-                    assert!(v1.coeffs == v2.coeffs);
-                }
-            }
-        }
-    }
-
-    let rng = &mut rand::thread_rng();
-
-    test_consistency(rng);
-}
+// Note: a lot of this file is copypasta from zkcrypto/bellman
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::ops::{AddAssign, MulAssign, SubAssign};
+use std::sync::{Arc, RwLock};
+
+use once_cell::sync::Lazy;
+
+use crate::polynomial::Polynomial;
+use crate::KZGError;
+use blstrs::{G1Projective, Scalar};
+use pairing::group::ff::Field;
+use pairing::group::ff::PrimeField;
+use pairing::group::Group as _;
+
+#[cfg(feature = "parallel")]
+use crate::utils::chunk_by_num_threads;
+#[cfg(feature = "parallel")]
+use crate::utils::log2;
+
+/// A domain's primitive `2^exp`-th root of unity together with the full
+/// forward/inverse twiddle tables derived from it (`[omega^0, omega^1,
+/// ...]`). Building this is the `Scalar::S`-length exponentiation loop
+/// `compute_omega` used to redo on every call, plus an `m`-length pass to
+/// fill the tables; caching it by `exp` means a prover that builds many
+/// same-size domains (e.g. per-round FFTs) pays that cost exactly once.
+struct RootOfUnity {
+    omega: Scalar,
+    omegainv: Scalar,
+    twiddles: Vec<Scalar>,
+    inv_twiddles: Vec<Scalar>,
+}
+
+static ROOT_OF_UNITY_CACHE: Lazy<RwLock<HashMap<u32, Arc<RootOfUnity>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// `Scalar::multiplicative_generator()`'s inverse, the same for every
+/// domain regardless of size -- computed once instead of on every
+/// `EvaluationDomain::new`/`from_coeffs` call.
+static GENINV: Lazy<Scalar> = Lazy::new(|| Scalar::multiplicative_generator().invert().unwrap());
+
+fn root_of_unity(exp: u32) -> Arc<RootOfUnity> {
+    if let Some(cached) = ROOT_OF_UNITY_CACHE.read().unwrap().get(&exp) {
+        return cached.clone();
+    }
+
+    let omega = Scalar::root_of_unity().pow_vartime(&[1 << (Scalar::S - exp)]);
+    let omegainv = omega.invert().unwrap();
+    let m = 1usize << exp;
+
+    let power_table = |root: Scalar| {
+        let mut table = Vec::with_capacity(m);
+        let mut cur = Scalar::one();
+        for _ in 0..m {
+            table.push(cur);
+            cur.mul_assign(&root);
+        }
+        table
+    };
+
+    let cached = Arc::new(RootOfUnity {
+        omega,
+        omegainv,
+        twiddles: power_table(omega),
+        inv_twiddles: power_table(omegainv),
+    });
+
+    ROOT_OF_UNITY_CACHE
+        .write()
+        .unwrap()
+        .insert(exp, cached.clone());
+
+    cached
+}
+
+/// A group over which the radix-2 FFT in this module can be run.
+///
+/// `Scalar` is the obvious instance (plain field multiplication), but
+/// `blstrs::G1Projective` also implements it so that `EvaluationDomain` can
+/// transform elliptic-curve points directly, e.g. to move an SRS between its
+/// monomial and Lagrange bases without a separate code path. The twiddle
+/// factors produced by the FFT are always `Scalar`, hence `group_scale`
+/// taking a `&Scalar` rather than `&Self`.
+pub trait FftGroup: Copy + Clone + Send + Sync + 'static {
+    fn group_zero() -> Self;
+    fn group_add(&mut self, other: &Self);
+    fn group_sub(&mut self, other: &Self);
+    fn group_scale(&mut self, by: &Scalar);
+
+    /// Runs the radix-2 FFT for this group. The default dispatches to the
+    /// CPU `serial_fft`/`parallel_fft` implementations below; `Scalar`
+    /// overrides this (behind the `cuda`/`opencl` features) to try the GPU
+    /// backend in [`crate::gpu`] first, falling back to the CPU path when
+    /// no device is available.
+    fn run_fft(a: &mut [Self], omega: &Scalar, log_n: u32) {
+        cpu_fft(a, omega, log_n)
+    }
+}
+
+impl FftGroup for Scalar {
+    fn group_zero() -> Self {
+        Scalar::zero()
+    }
+
+    fn group_add(&mut self, other: &Self) {
+        self.add_assign(other);
+    }
+
+    fn group_sub(&mut self, other: &Self) {
+        self.sub_assign(other);
+    }
+
+    fn group_scale(&mut self, by: &Scalar) {
+        self.mul_assign(by);
+    }
+
+    #[cfg(any(feature = "cuda", feature = "opencl"))]
+    fn run_fft(a: &mut [Self], omega: &Scalar, log_n: u32) {
+        crate::gpu::best_fft(a, omega, log_n)
+    }
+}
+
+impl FftGroup for G1Projective {
+    fn group_zero() -> Self {
+        G1Projective::identity()
+    }
+
+    fn group_add(&mut self, other: &Self) {
+        *self += other;
+    }
+
+    fn group_sub(&mut self, other: &Self) {
+        *self -= other;
+    }
+
+    fn group_scale(&mut self, by: &Scalar) {
+        *self *= by;
+    }
+}
+
+/// Marker trait for the basis a set of `EvaluationDomain` coefficients is
+/// expressed in. Carrying this as a zero-cost phantom type parameter lets
+/// the compiler reject mixing coefficient-form and evaluation-form data
+/// (e.g. calling `ifft` twice, or `sub_assign` across two different bases)
+/// instead of relying on the caller to track it by convention.
+pub trait Basis: Copy + Clone + std::fmt::Debug + PartialEq + Eq + Send + Sync + 'static {}
+
+/// Coefficients of a polynomial in monomial form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Coeff;
+impl Basis for Coeff {}
+
+/// Evaluations of a polynomial over the domain's `2^exp`-th roots of unity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LagrangeCoeff;
+impl Basis for LagrangeCoeff {}
+
+/// Evaluations of a polynomial over a coset of the domain, used when
+/// dividing the quotient polynomial by the vanishing polynomial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtendedLagrangeCoeff;
+impl Basis for ExtendedLagrangeCoeff {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvaluationDomain<G: FftGroup = Scalar, B: Basis = Coeff> {
+    pub(crate) coeffs: Vec<G>,
+    pub(crate) d: usize,
+    pub(crate) exp: u32,
+    pub(crate) omega: Scalar,
+    pub(crate) omegainv: Scalar,
+    pub(crate) geninv: Scalar,
+    pub(crate) minv: Scalar,
+    pub(crate) _marker: PhantomData<B>,
+}
+
+impl From<EvaluationDomain<Scalar, Coeff>> for Polynomial {
+    fn from(domain: EvaluationDomain<Scalar, Coeff>) -> Polynomial {
+        Polynomial::new(domain.coeffs)
+    }
+}
+
+impl<G: FftGroup, B: Basis> AsRef<[G]> for EvaluationDomain<G, B> {
+    fn as_ref(&self) -> &[G] {
+        &self.coeffs
+    }
+}
+
+impl<G: FftGroup, B: Basis> AsMut<[G]> for EvaluationDomain<G, B> {
+    fn as_mut(&mut self) -> &mut [G] {
+        &mut self.coeffs
+    }
+}
+
+impl<G: FftGroup, B: Basis> EvaluationDomain<G, B> {
+    pub fn into_coeffs(self) -> Vec<G> {
+        self.coeffs
+    }
+
+    pub fn len(&self) -> usize {
+        self.coeffs.len()
+    }
+
+    pub fn clone_with_different_coeffs(&self, coeffs: Vec<G>) -> EvaluationDomain<G, B> {
+        EvaluationDomain {
+            coeffs,
+            d: self.d,
+            exp: self.exp,
+            omega: self.omega,
+            omegainv: self.omegainv,
+            geninv: self.geninv,
+            minv: self.minv,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Multiply every coefficient `i` by `g^i`, shifting evaluations onto (or
+    /// off of) a coset. This does not change the basis marker: it's the
+    /// shared building block `coset_fft`/`icoset_fft` wrap around the actual
+    /// radix-2 transform to move to/from `ExtendedLagrangeCoeff`.
+    pub fn distribute_powers(&mut self, g: Scalar) {
+        #[cfg(feature = "parallel")]
+        rayon::scope(|scope| {
+            let chunk_size = chunk_by_num_threads(self.coeffs.len());
+
+            for (i, v) in self.coeffs.chunks_mut(chunk_size).enumerate() {
+                scope.spawn(move |_scope| {
+                    let mut u = g.pow_vartime(&[(i * chunk_size) as u64]);
+                    for v in v.iter_mut() {
+                        v.group_scale(&u);
+                        u.mul_assign(&g);
+                    }
+                });
+            }
+        });
+
+        #[cfg(not(feature = "parallel"))]
+        {
+            for (i, v) in self.coeffs.iter_mut().enumerate() {
+                let mut u = g.pow_vartime(&[i as u64]);
+                v.group_scale(&u);
+                u.mul_assign(&g);
+            }
+        };
+    }
+
+    /// This evaluates t(tau) for this domain, which is
+    /// tau^m - 1 for these radix-2 domains.
+    pub fn z(&self, tau: &Scalar) -> Scalar {
+        let mut tmp = tau.pow_vartime(&[self.coeffs.len() as u64]);
+        tmp.sub_assign(&Scalar::one());
+
+        tmp
+    }
+
+    /// Perform O(n) subtraction of one polynomial from another in the
+    /// domain. Both operands must already share the same basis `B`, which
+    /// the compiler enforces via the shared type parameter.
+    pub fn sub_assign(&mut self, other: &EvaluationDomain<G, B>) {
+        assert_eq!(self.coeffs.len(), other.coeffs.len());
+
+        #[cfg(feature = "parallel")]
+        rayon::scope(|scope| {
+            let chunk_size = chunk_by_num_threads(self.coeffs.len());
+
+            for (a, b) in self
+                .coeffs
+                .chunks_mut(chunk_size)
+                .zip(other.coeffs.chunks(chunk_size))
+            {
+                scope.spawn(move |_scope| {
+                    for (a, b) in a.iter_mut().zip(b.iter()) {
+                        a.group_sub(b);
+                    }
+                });
+            }
+        });
+
+        #[cfg(not(feature = "parallel"))]
+        for (a, b) in self.coeffs.iter_mut().zip(other.coeffs.iter()) {
+            a.group_sub(b);
+        }
+    }
+}
+
+impl<G: FftGroup> EvaluationDomain<G, Coeff> {
+    // returns m, exp, and omega
+    pub fn compute_omega(d: usize) -> Result<(usize, u32, Scalar), KZGError> {
+        // Compute the size of our evaluation domain
+        let mut m = 1;
+        let mut exp = 0;
+
+        while m < d {
+            m *= 2;
+            exp += 1;
+
+            // The pairing-friendly curve may not be able to support
+            // large enough (radix2) evaluation domains.
+            if exp >= Scalar::S {
+                return Err(KZGError::PolynomialDegreeTooLarge);
+            }
+        }
+
+        // The 2^exp primitive root of unity, cached by `exp` since many
+        // domains of the same size share it.
+        let omega = root_of_unity(exp).omega;
+
+        Ok((m, exp, omega))
+    }
+
+    /// Builds a domain directly from coefficients in monomial form, i.e.
+    /// data that has not yet been transformed by `fft`. Callers with
+    /// already-evaluated data should go through `fft`/`coset_fft` instead,
+    /// which carry the resulting basis in the type.
+    pub fn new(coeffs: Vec<G>, d: usize, exp: u32, omega: Scalar) -> Self {
+        let mut inverses = [omega, Scalar::from(d as u64)];
+        batch_invert(&mut inverses);
+        let [omegainv, minv] = inverses;
+
+        EvaluationDomain {
+            coeffs,
+            d,
+            exp,
+            omega,
+            omegainv,
+            geninv: *GENINV,
+            minv,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn from_coeffs(mut coeffs: Vec<G>) -> Result<EvaluationDomain<G, Coeff>, KZGError> {
+        let (m, exp, omega) = Self::compute_omega(coeffs.len())?;
+
+        // Extend the coeffs vector with zeroes if necessary
+        coeffs.resize(m, G::group_zero());
+
+        let mut inverses = [omega, Scalar::from(m as u64)];
+        batch_invert(&mut inverses);
+        let [omegainv, minv] = inverses;
+
+        Ok(EvaluationDomain {
+            d: m,
+            coeffs,
+            exp,
+            omega,
+            omegainv,
+            geninv: *GENINV,
+            minv,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Transform coefficients in monomial form into evaluations over the
+    /// domain's roots of unity.
+    pub fn fft(self) -> EvaluationDomain<G, LagrangeCoeff> {
+        let EvaluationDomain {
+            mut coeffs,
+            d,
+            exp,
+            omega,
+            omegainv,
+            geninv,
+            minv,
+            ..
+        } = self;
+
+        best_fft(&mut coeffs, &omega, exp);
+
+        EvaluationDomain {
+            coeffs,
+            d,
+            exp,
+            omega,
+            omegainv,
+            geninv,
+            minv,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Transform coefficients in monomial form into evaluations over a
+    /// coset of the domain, for dividing the quotient polynomial by the
+    /// vanishing polynomial.
+    pub fn coset_fft(self) -> EvaluationDomain<G, ExtendedLagrangeCoeff> {
+        let mut this = self;
+        this.distribute_powers(Scalar::multiplicative_generator());
+
+        let EvaluationDomain {
+            mut coeffs,
+            d,
+            exp,
+            omega,
+            omegainv,
+            geninv,
+            minv,
+            ..
+        } = this;
+
+        best_fft(&mut coeffs, &omega, exp);
+
+        EvaluationDomain {
+            coeffs,
+            d,
+            exp,
+            omega,
+            omegainv,
+            geninv,
+            minv,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<G: FftGroup> EvaluationDomain<G, LagrangeCoeff> {
+    /// Transform evaluations over the domain's roots of unity back into
+    /// coefficients in monomial form.
+    pub fn ifft(self) -> EvaluationDomain<G, Coeff> {
+        let EvaluationDomain {
+            mut coeffs,
+            d,
+            exp,
+            omega,
+            omegainv,
+            geninv,
+            minv,
+            ..
+        } = self;
+
+        best_fft(&mut coeffs, &omegainv, exp);
+        scale_by(&mut coeffs, minv);
+
+        EvaluationDomain {
+            coeffs,
+            d,
+            exp,
+            omega,
+            omegainv,
+            geninv,
+            minv,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<G: FftGroup> EvaluationDomain<G, ExtendedLagrangeCoeff> {
+    /// Transform evaluations over a coset of the domain back into
+    /// coefficients in monomial form.
+    pub fn icoset_fft(self) -> EvaluationDomain<G, Coeff> {
+        let EvaluationDomain {
+            mut coeffs,
+            d,
+            exp,
+            omega,
+            omegainv,
+            geninv,
+            minv,
+            ..
+        } = self;
+
+        best_fft(&mut coeffs, &omegainv, exp);
+        scale_by(&mut coeffs, minv);
+
+        let mut out = EvaluationDomain {
+            coeffs,
+            d,
+            exp,
+            omega,
+            omegainv,
+            geninv,
+            minv,
+            _marker: PhantomData,
+        };
+        out.distribute_powers(geninv);
+        out
+    }
+
+    /// The target polynomial is the zero polynomial in our
+    /// evaluation domain, so we must perform division over
+    /// a coset. Only meaningful on coset evaluations: dividing
+    /// coefficient-form or plain-domain evaluations by Z would silently
+    /// produce garbage, which is exactly the class of bug pinning this to
+    /// `ExtendedLagrangeCoeff` rules out.
+    pub fn divide_by_z_on_coset(&mut self) {
+        let i = self
+            .z(&Scalar::multiplicative_generator())
+            .invert()
+            .unwrap();
+
+        #[cfg(feature = "parallel")]
+        rayon::scope(|scope| {
+            let chunk_size = chunk_by_num_threads(self.coeffs.len());
+
+            for v in self.coeffs.chunks_mut(chunk_size) {
+                scope.spawn(move |_scope| {
+                    for v in v {
+                        v.group_scale(&i);
+                    }
+                });
+            }
+        });
+
+        #[cfg(not(feature = "parallel"))]
+        {
+            for v in self.coeffs.iter_mut() {
+                v.group_scale(&i);
+            }
+        }
+    }
+}
+
+impl<B: Basis> EvaluationDomain<Scalar, B> {
+    /// Perform O(n) multiplication of two polynomials in the domain.
+    ///
+    /// This is only meaningful for scalar domains: there is no general
+    /// notion of multiplying two elements of an arbitrary `FftGroup`
+    /// together (e.g. two elliptic-curve points), only scaling one by a
+    /// `Scalar`. Both operands must share the same basis `B`.
+    pub fn mul_assign(&mut self, other: &EvaluationDomain<Scalar, B>) {
+        assert_eq!(self.coeffs.len(), other.coeffs.len());
+
+        #[cfg(feature = "parallel")]
+        rayon::scope(|scope| {
+            let chunk_size = chunk_by_num_threads(self.coeffs.len());
+
+            for (a, b) in self
+                .coeffs
+                .chunks_mut(chunk_size)
+                .zip(other.coeffs.chunks(chunk_size))
+            {
+                scope.spawn(move |_scope| {
+                    for (a, b) in a.iter_mut().zip(b.iter()) {
+                        a.mul_assign(b);
+                    }
+                });
+            }
+        });
+
+        #[cfg(not(feature = "parallel"))]
+        for (a, b) in self.coeffs.iter_mut().zip(other.coeffs.iter()) {
+            a.mul_assign(b);
+        }
+    }
+
+    /// The barycentric Lagrange weights `L_i(z)` for every root of unity
+    /// `omega^i` in this domain, evaluated at `z`. Verifying a KZG opening
+    /// at many points needs one such vector per point; computing each
+    /// `L_i(z)` independently would cost `n` field inversions per point,
+    /// but every term only differs in its `z - omega^i` denominator, so
+    /// `batch_invert` amortizes all of them into a single inversion.
+    pub fn lagrange_coeffs(&self, z: &Scalar) -> Vec<Scalar> {
+        let n = self.coeffs.len();
+
+        let mut denoms = Vec::with_capacity(n);
+        let mut omega_pow = Scalar::one();
+        for _ in 0..n {
+            let mut denom = *z;
+            denom.sub_assign(&omega_pow);
+            denoms.push(denom);
+            omega_pow.mul_assign(&self.omega);
+        }
+
+        // If `z` coincides with one of the domain's roots of unity, the
+        // barycentric formula below hits 0/0: that root's denominator and
+        // the shared `(z^n - 1) / n` factor both vanish, and `batch_invert`
+        // leaves the zero denominator as zero rather than inverting it.
+        // Left unhandled that silently produces an all-zero weight vector;
+        // the correct answer is the indicator for that root (`L_i(z) = 1`,
+        // every other `L_j(z) = 0`), so special-case it.
+        if let Some(i) = denoms.iter().position(|d| bool::from(d.is_zero())) {
+            let mut weights = vec![Scalar::zero(); n];
+            weights[i] = Scalar::one();
+            return weights;
+        }
+
+        batch_invert(&mut denoms);
+
+        // Every term shares the factor (z^n - 1) / n.
+        let mut shared = self.z(z);
+        shared.mul_assign(&self.minv);
+
+        let mut omega_pow = Scalar::one();
+        for denom in denoms.iter_mut() {
+            let mut l = shared;
+            l.mul_assign(&omega_pow);
+            l.mul_assign(denom);
+            *denom = l;
+            omega_pow.mul_assign(&self.omega);
+        }
+
+        denoms
+    }
+}
+
+/// Invert every nonzero element of `elements` in place using a single field
+/// inversion instead of one per element. Computes the running products
+/// `acc_i = x_0 * x_1 * ... * x_i`, inverts the final product once, then
+/// walks backward recovering each individual inverse from the stored
+/// prefix product and the shared inverse. Zero elements are left as zero,
+/// since they have no inverse and including them in the running product
+/// would make it permanently zero.
+pub(crate) fn batch_invert(elements: &mut [Scalar]) {
+    let mut prefix_products = Vec::with_capacity(elements.len());
+    let mut acc = Scalar::one();
+    for element in elements.iter() {
+        prefix_products.push(acc);
+        if !bool::from(element.is_zero()) {
+            acc.mul_assign(element);
+        }
+    }
+
+    // acc is now the product of every nonzero element; invert it once.
+    let mut acc_inv = acc.invert().unwrap();
+
+    for (element, prefix) in elements.iter_mut().zip(prefix_products.into_iter()).rev() {
+        if bool::from(element.is_zero()) {
+            continue;
+        }
+
+        let mut inv = acc_inv;
+        inv.mul_assign(&prefix);
+        acc_inv.mul_assign(element);
+        *element = inv;
+    }
+}
+
+fn scale_by<G: FftGroup>(coeffs: &mut [G], by: Scalar) {
+    #[cfg(feature = "parallel")]
+    rayon::scope(|scope| {
+        let chunk_size = chunk_by_num_threads(coeffs.len());
+
+        for v in coeffs.chunks_mut(chunk_size) {
+            scope.spawn(move |_scope| {
+                for v in v {
+                    v.group_scale(&by);
+                }
+            });
+        }
+    });
+
+    #[cfg(not(feature = "parallel"))]
+    for v in coeffs.iter_mut() {
+        v.group_scale(&by);
+    }
+}
+
+/// Entry point used throughout this module: dispatches to whatever
+/// `G::run_fft` resolves to, which is the CPU path below for every `G`
+/// except a GPU-accelerated `Scalar` build.
+fn best_fft<G: FftGroup>(a: &mut [G], omega: &Scalar, log_n: u32) {
+    G::run_fft(a, omega, log_n);
+}
+
+pub(crate) fn cpu_fft<G: FftGroup>(a: &mut [G], omega: &Scalar, log_n: u32) {
+    #[cfg(feature = "parallel")]
+    {
+        let log_cpus = log2(rayon::current_num_threads() as u64) as u32;
+
+        if log_n <= log_cpus {
+            serial_fft(a, omega, log_n);
+        } else {
+            parallel_fft(a, omega, log_n, log_cpus);
+        }
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    serial_fft(a, omega, log_n);
+}
+
+fn bitreverse(mut n: u32, l: u32) -> u32 {
+    let mut r = 0;
+    for _ in 0..l {
+        r = (r << 1) | (n & 1);
+        n >>= 1;
+    }
+    r
+}
+
+#[allow(clippy::many_single_char_names)]
+pub(crate) fn serial_fft<G: FftGroup>(a: &mut [G], omega: &Scalar, log_n: u32) {
+    let n = a.len() as u32;
+    assert_eq!(n, 1 << log_n);
+
+    // `serial_fft` also runs the reduced-size sub-FFTs inside
+    // `parallel_fft`. Their root of unity, `omega^(2^log_cpus)`, is in
+    // fact the same canonical root `root_of_unity(log_new_n).omega` would
+    // produce directly, so those recursive calls hit the cached table too,
+    // not just the top-level one.
+    //
+    // `root_of_unity` computes `1 << (Scalar::S - log_n)`, which underflows
+    // for `log_n > Scalar::S`. `compute_omega` never produces a domain with
+    // `log_n >= Scalar::S`, but `serial_fft` is `pub(crate)` and reachable directly,
+    // so guard here too rather than relying on every caller to enforce it.
+    if log_n < Scalar::S {
+        let cached = root_of_unity(log_n);
+        let table = if *omega == cached.omega {
+            Some(&cached.twiddles)
+        } else if *omega == cached.omegainv {
+            Some(&cached.inv_twiddles)
+        } else {
+            None
+        };
+
+        if let Some(table) = table {
+            serial_fft_with_table(a, table, log_n);
+            return;
+        }
+    }
+
+    for k in 0..n {
+        let rk = bitreverse(k, log_n);
+        if k < rk {
+            a.swap(rk as usize, k as usize);
+        }
+    }
+
+    let mut m = 1;
+    for _ in 0..log_n {
+        let w_m = omega.pow_vartime(&[u64::from(n / (2 * m))]);
+
+        let mut k = 0;
+        while k < n {
+            let mut w = Scalar::one();
+            for j in 0..m {
+                let mut t = a[(k + j + m) as usize];
+                t.group_scale(&w);
+                let mut tmp = a[(k + j) as usize];
+                tmp.group_sub(&t);
+                a[(k + j + m) as usize] = tmp;
+                a[(k + j) as usize].group_add(&t);
+                w.mul_assign(&w_m);
+            }
+
+            k += 2 * m;
+        }
+
+        m *= 2;
+    }
+}
+
+// Same butterfly network as `serial_fft`, but reading each level's twiddle
+// factors out of a precomputed `[omega^0, omega^1, ...]` table (see
+// `root_of_unity`) instead of recomputing `w_m` via `pow_vartime` and
+// walking `w` forward with a multiplication per butterfly.
+#[allow(clippy::many_single_char_names)]
+fn serial_fft_with_table<G: FftGroup>(a: &mut [G], twiddles: &[Scalar], log_n: u32) {
+    let n = a.len() as u32;
+
+    for k in 0..n {
+        let rk = bitreverse(k, log_n);
+        if k < rk {
+            a.swap(rk as usize, k as usize);
+        }
+    }
+
+    let mut m = 1;
+    for _ in 0..log_n {
+        let stride = n / (2 * m);
+
+        let mut k = 0;
+        while k < n {
+            for j in 0..m {
+                let w = twiddles[(j * stride) as usize];
+                let mut t = a[(k + j + m) as usize];
+                t.group_scale(&w);
+                let mut tmp = a[(k + j) as usize];
+                tmp.group_sub(&t);
+                a[(k + j + m) as usize] = tmp;
+                a[(k + j) as usize].group_add(&t);
+            }
+
+            k += 2 * m;
+        }
+
+        m *= 2;
+    }
+}
+
+#[cfg(feature = "parallel")]
+pub(crate) fn parallel_fft<G: FftGroup>(a: &mut [G], omega: &Scalar, log_n: u32, log_cpus: u32) {
+    assert!(log_n >= log_cpus);
+
+    let num_cpus = 1 << log_cpus;
+    let log_new_n = log_n - log_cpus;
+    let mut tmp = vec![vec![G::group_zero(); 1 << log_new_n]; num_cpus];
+    let new_omega = omega.pow_vartime(&[num_cpus as u64]);
+
+    rayon::scope(|scope| {
+        let a = &*a;
+
+        for (j, tmp) in tmp.iter_mut().enumerate() {
+            scope.spawn(move |_scope| {
+                // Shuffle into a sub-FFT
+                let omega_j = omega.pow_vartime(&[j as u64]);
+                let omega_step = omega.pow_vartime(&[(j as u64) << log_new_n]);
+
+                let mut elt = Scalar::one();
+                for (i, tmp) in tmp.iter_mut().enumerate() {
+                    for s in 0..num_cpus {
+                        let idx = (i + (s << log_new_n)) % (1 << log_n);
+                        let mut t = a[idx];
+                        t.group_scale(&elt);
+                        tmp.group_add(&t);
+                        elt.mul_assign(&omega_step);
+                    }
+                    elt.mul_assign(&omega_j);
+                }
+
+                // Perform sub-FFT
+                serial_fft(tmp, &new_omega, log_new_n);
+            });
+        }
+    });
+
+    // TODO: does this hurt or help?
+    rayon::scope(|scope| {
+        let chunk_size = chunk_by_num_threads(a.len());
+        let tmp = &tmp;
+
+        for (idx, a) in a.chunks_mut(chunk_size).enumerate() {
+            scope.spawn(move |_scope| {
+                let mut idx = idx * chunk_size;
+                let mask = (1 << log_cpus) - 1;
+                for a in a {
+                    *a = tmp[idx & mask][idx >> log_cpus];
+                    idx += 1;
+                }
+            });
+        }
+    });
+}
+
+#[cfg(all(feature = "serde_support", feature = "b12_381"))]
+use crate::wrapper_types::SerializablePrimeField;
+
+#[cfg(all(feature = "serde_support", feature = "b12_381"))]
+use bls12_381::Scalar;
+
+#[cfg(all(feature = "serde_support", feature = "b12_381"))]
+#[derive(Serialize, Deserialize)]
+pub struct SerializableEvaluationDomain {
+    coeffs: Vec<SerializablePrimeField<Scalar>>,
+    exp: u32,
+    omega: SerializablePrimeField<Scalar>,
+    omegainv: SerializablePrimeField<Scalar>,
+    geninv: SerializablePrimeField<Scalar>,
+    minv: SerializablePrimeField<Scalar>,
+}
+
+#[cfg(test)]
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+// Test multiplying various (low degree) polynomials together and
+// comparing with naive evaluations.
+#[test]
+fn polynomial_arith() {
+    fn test_mul<R: Rng>(mut rng: &mut R) {
+        for coeffs_a in vec![1, 5, 10, 50] {
+            for coeffs_b in vec![1, 5, 10, 50] {
+                let a: Vec<_> = (0..coeffs_a).map(|_| Scalar::random(&mut rng)).collect();
+                let b: Vec<_> = (0..coeffs_b).map(|_| Scalar::random(&mut rng)).collect();
+
+                let a = Polynomial::new_from_coeffs(a, coeffs_a - 1);
+                let b = Polynomial::new_from_coeffs(b, coeffs_b - 1);
+
+                // naive evaluation
+                let naive = a.clone() * b.clone();
+                let fft = a.fft_mul(&b);
+
+                assert!(naive == fft);
+            }
+        }
+    }
+
+    let rng = &mut SmallRng::from_seed([42; 32]);
+
+    test_mul(rng);
+}
+
+#[cfg(test)]
+fn random_evals(rng: &mut SmallRng, d: usize) -> EvaluationDomain<Scalar, Coeff> {
+    let mut coeffs = vec![Scalar::zero(); d];
+
+    for i in 0..d {
+        coeffs[i] = rng.gen::<u64>().into();
+    }
+
+    EvaluationDomain::from_coeffs(coeffs).unwrap()
+}
+
+#[test]
+fn fft_composition() {
+    use rand::RngCore;
+
+    fn test_comp<R: RngCore>(mut rng: &mut R) {
+        for coeffs in 0..10 {
+            let coeffs = 1 << coeffs;
+
+            let mut v = vec![];
+            for _ in 0..coeffs {
+                v.push(Scalar::random(&mut rng));
+            }
+
+            let domain: EvaluationDomain<Scalar, Coeff> =
+                EvaluationDomain::from_coeffs(v.clone()).unwrap();
+
+            let round_tripped = domain.clone().fft().ifft();
+            assert!(v == round_tripped.coeffs);
+
+            let coset_round_tripped = domain.coset_fft().icoset_fft();
+            assert!(v == coset_round_tripped.coeffs);
+        }
+    }
+
+    let rng = &mut rand::thread_rng();
+
+    test_comp(rng);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_fft_consistency() {
+    use rand::RngCore;
+    use std::cmp::min;
+
+    fn test_consistency<R: RngCore>(mut rng: &mut R) {
+        for _ in 0..5 {
+            for log_d in 0..10 {
+                let d = 1 << log_d;
+
+                let v1 = (0..d).map(|_| Scalar::random(&mut rng)).collect::<Vec<_>>();
+                let mut v1: EvaluationDomain<Scalar, Coeff> =
+                    EvaluationDomain::from_coeffs(v1).unwrap();
+                let mut v2: EvaluationDomain<Scalar, Coeff> =
+                    EvaluationDomain::from_coeffs(v1.coeffs.clone()).unwrap();
+
+                for log_cpus in log_d..min(log_d + 1, 3) {
+                    parallel_fft(&mut v1.coeffs, &v1.omega, log_d, log_cpus);
+                    serial_fft(&mut v2.coeffs, &v2.omega, log_d);
+
+                    assert!(v1.coeffs == v2.coeffs);
+                }
+            }
+        }
+    }
+
+    let rng = &mut rand::thread_rng();
+
+    test_consistency(rng);
+}
+
+// An FFT over group elements should agree with the same FFT run on the
+// scalars obtained by taking each input point to be `coeff_i * G` for a
+// fixed generator `G`: the FFT is linear, so transforming the points and
+// then fixing the generator back in commutes with doing it the other way
+// around.
+#[test]
+fn fft_over_group_matches_scalar() {
+    use rand::RngCore;
+
+    fn test_group<R: RngCore>(mut rng: &mut R) {
+        for log_d in 0..6 {
+            let d = 1usize << log_d;
+
+            let scalars: Vec<Scalar> = (0..d).map(|_| Scalar::random(&mut rng)).collect();
+            let points: Vec<G1Projective> = scalars
+                .iter()
+                .map(|s| G1Projective::generator() * s)
+                .collect();
+
+            let scalar_domain: EvaluationDomain<Scalar, Coeff> =
+                EvaluationDomain::from_coeffs(scalars).unwrap();
+            let point_domain: EvaluationDomain<G1Projective, Coeff> =
+                EvaluationDomain::from_coeffs(points).unwrap();
+
+            let scalar_domain = scalar_domain.fft();
+            let point_domain = point_domain.fft();
+
+            for (s, p) in scalar_domain.coeffs.iter().zip(point_domain.coeffs.iter()) {
+                assert_eq!(G1Projective::generator() * s, *p);
+            }
+        }
+    }
+
+    let rng = &mut rand::thread_rng();
+
+    test_group(rng);
+}
+
+#[test]
+fn batch_invert_matches_individual_inversion() {
+    let rng = &mut rand::thread_rng();
+
+    let mut elements: Vec<Scalar> = (0..32).map(|_| Scalar::random(&mut *rng)).collect();
+    elements[0] = Scalar::zero();
+
+    let expected: Vec<Scalar> = elements
+        .iter()
+        .map(|e| {
+            if bool::from(e.is_zero()) {
+                Scalar::zero()
+            } else {
+                e.invert().unwrap()
+            }
+        })
+        .collect();
+
+    batch_invert(&mut elements);
+
+    assert_eq!(elements, expected);
+}
+
+#[test]
+fn lagrange_coeffs_interpolate_at_z() {
+    use rand::RngCore;
+
+    fn test_interpolation<R: RngCore>(mut rng: &mut R) {
+        for log_d in 1..6 {
+            let d = 1usize << log_d;
+
+            let coeffs: Vec<Scalar> = (0..d).map(|_| Scalar::random(&mut rng)).collect();
+            let domain: EvaluationDomain<Scalar, Coeff> =
+                EvaluationDomain::from_coeffs(coeffs.clone()).unwrap();
+            let evals = EvaluationDomain::from_coeffs(coeffs)
+                .unwrap()
+                .fft()
+                .coeffs;
+
+            let z = Scalar::random(&mut rng);
+            let lagrange_coeffs = domain.lagrange_coeffs(&z);
+
+            let mut interpolated = Scalar::zero();
+            for (l, eval) in lagrange_coeffs.iter().zip(evals.iter()) {
+                let mut term = *l;
+                term.mul_assign(eval);
+                interpolated.add_assign(&term);
+            }
+
+            // Evaluate the original polynomial at z directly, via Horner's method.
+            let mut expected = Scalar::zero();
+            for coeff in domain.coeffs.iter().rev() {
+                expected.mul_assign(&z);
+                expected.add_assign(coeff);
+            }
+
+            assert_eq!(interpolated, expected);
+        }
+    }
+
+    let rng = &mut rand::thread_rng();
+
+    test_interpolation(rng);
+}
+
+#[test]
+fn lagrange_coeffs_at_domain_root_is_indicator() {
+    let coeffs: Vec<Scalar> = (0..8u64).map(Scalar::from).collect();
+    let domain: EvaluationDomain<Scalar, Coeff> =
+        EvaluationDomain::from_coeffs(coeffs).unwrap();
+
+    let mut omega_pow = Scalar::one();
+    for i in 0..domain.len() {
+        let weights = domain.lagrange_coeffs(&omega_pow);
+
+        for (j, w) in weights.iter().enumerate() {
+            if j == i {
+                assert_eq!(*w, Scalar::one());
+            } else {
+                assert_eq!(*w, Scalar::zero());
+            }
+        }
+
+        omega_pow.mul_assign(&domain.omega);
+    }
+}
+
+// `fft`/`ifft` are exercised as round trips elsewhere; this checks the
+// cached, indexed butterfly path in `serial_fft_with_table` against an
+// independent naive DFT, so a bug in the twiddle-table indexing (as
+// opposed to just `fft`/`ifft` disagreeing with each other) wouldn't slip
+// through.
+#[test]
+fn fft_matches_naive_dft() {
+    fn naive_dft(coeffs: &[Scalar], omega: &Scalar) -> Vec<Scalar> {
+        let n = coeffs.len();
+        (0..n)
+            .map(|i| {
+                let mut acc = Scalar::zero();
+                let root_i = omega.pow_vartime(&[i as u64]);
+                let mut root_pow = Scalar::one();
+                for coeff in coeffs {
+                    let mut term = *coeff;
+                    term.mul_assign(&root_pow);
+                    acc.add_assign(&term);
+                    root_pow.mul_assign(&root_i);
+                }
+                acc
+            })
+            .collect()
+    }
+
+    let rng = &mut rand::thread_rng();
+
+    for log_d in 0..6 {
+        let d = 1usize << log_d;
+        let coeffs: Vec<Scalar> = (0..d).map(|_| Scalar::random(&mut *rng)).collect();
+
+        let domain: EvaluationDomain<Scalar, Coeff> =
+            EvaluationDomain::from_coeffs(coeffs.clone()).unwrap();
+        let omega = domain.omega;
+
+        let expected = naive_dft(&coeffs, &omega);
+        let actual = domain.fft().coeffs;
+
+        assert_eq!(actual, expected);
+    }
+}
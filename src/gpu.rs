@@ -0,0 +1,100 @@
+// Note: mirrors bellperson's integration of `ec-gpu-gen` for FFT offload.
+// This module only compiles under the `cuda`/`opencl` features, so the
+// default (CPU-only) build of this crate never pulls in a GPU toolchain.
+
+use std::sync::Mutex;
+
+use blstrs::Scalar;
+use ec_gpu_gen::{fft::FftKernel, rust_gpu_tools::Device};
+use once_cell::sync::OnceCell;
+
+use crate::ft::cpu_fft;
+
+/// Below this domain size the kernel launch overhead dwarfs whatever the
+/// GPU saves over the CPU radix-2 FFT, so `best_fft` doesn't even attempt
+/// to acquire the device.
+const GPU_MIN_LOG_N: u32 = 16;
+
+/// The device + compiled NTT kernel, initialized once on first use and
+/// cached for the life of the process so that repeated `fft`/`ifft` calls
+/// in a proving loop don't re-upload the kernel program every time. `None`
+/// means no compatible device was found, in which case every call falls
+/// back to the CPU path.
+static KERNEL: OnceCell<Mutex<Option<FftKernel<'static, Scalar>>>> = OnceCell::new();
+
+fn kernel() -> &'static Mutex<Option<FftKernel<'static, Scalar>>> {
+    KERNEL.get_or_init(|| {
+        let kernel = Device::all()
+            .first()
+            .and_then(|device| FftKernel::create(device).ok());
+
+        Mutex::new(kernel)
+    })
+}
+
+/// Runs the radix-2 NTT on-device when a GPU is available and the domain
+/// is large enough to be worth it, falling back to `cpu_fft`
+/// (`serial_fft`/`parallel_fft`) otherwise. `EvaluationDomain`'s public API
+/// is unaffected either way -- this is purely a `best_fft` backend.
+pub(crate) fn best_fft(a: &mut [Scalar], omega: &Scalar, log_n: u32) {
+    if log_n >= GPU_MIN_LOG_N {
+        let mut guard = kernel().lock().unwrap();
+        if let Some(kernel) = guard.as_mut() {
+            if kernel.radix_fft(a, omega, log_n).is_ok() {
+                return;
+            }
+        }
+    }
+
+    cpu_fft(a, omega, log_n);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ft::serial_fft;
+    use crate::ft::EvaluationDomain;
+    use pairing::group::ff::Field;
+    use rand::RngCore;
+
+    fn assert_consistent<R: RngCore>(mut rng: &mut R, log_d: u32) {
+        let d = 1usize << log_d;
+
+        let coeffs: Vec<Scalar> = (0..d).map(|_| Scalar::random(&mut rng)).collect();
+        let (_, _, omega) = EvaluationDomain::<Scalar>::compute_omega(d).unwrap();
+
+        let mut gpu_out = coeffs.clone();
+        best_fft(&mut gpu_out, &omega, log_d);
+
+        let mut cpu_out = coeffs;
+        serial_fft(&mut cpu_out, &omega, log_d);
+
+        assert_eq!(gpu_out, cpu_out);
+    }
+
+    // Whether or not a device is actually present, `best_fft` must agree
+    // with `serial_fft` -- either it ran the same transform on-device, or
+    // it fell back to the CPU path outright.
+    #[test]
+    fn gpu_fft_consistency() {
+        let rng = &mut rand::thread_rng();
+
+        for log_d in 0..10 {
+            assert_consistent(rng, log_d);
+        }
+    }
+
+    // `gpu_fft_consistency` above never actually drives `kernel.radix_fft`,
+    // since `GPU_MIN_LOG_N = 16` and it only covers `log_d < 10`. This
+    // exercises the on-device path directly (when a GPU is actually
+    // present -- `#[ignore]`d by default since CI/dev machines without a
+    // `cuda`/`opencl` device would otherwise silently fall back to the CPU
+    // path and give a false pass).
+    #[test]
+    #[ignore = "requires a physical GPU device"]
+    fn gpu_fft_consistency_above_threshold() {
+        let rng = &mut rand::thread_rng();
+
+        assert_consistent(rng, GPU_MIN_LOG_N);
+    }
+}
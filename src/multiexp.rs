@@ -0,0 +1,179 @@
+// Note: the bucket-method multiexp below is adapted from halo2's
+// `multiexp_serial`.
+
+use blstrs::{G1Affine, G1Projective, Scalar};
+use pairing::group::ff::PrimeField;
+use pairing::group::Group;
+
+/// Computes `sum_i coeffs[i] * bases[i]` using Pippenger's bucket method.
+///
+/// Committing a degree-`n` polynomial to the SRS is the hot path of this
+/// crate, and a naive per-term scalar multiplication costs `O(n)` full
+/// point multiplications. Bucketing by `c`-bit windows of the scalars
+/// instead brings that down to roughly `O(n / log n)`.
+pub fn multiexp(coeffs: &[Scalar], bases: &[G1Affine]) -> G1Projective {
+    assert_eq!(coeffs.len(), bases.len());
+
+    let c = window_size(coeffs.len());
+
+    let mut acc = G1Projective::identity();
+    multiexp_serial(coeffs, bases, c, &mut acc);
+    acc
+}
+
+// Walks every `c`-bit segment from most- to least-significant, doubling
+// the accumulator `c` times between segments so each one lands at its
+// proper scale before the next (less significant) segment is folded in.
+fn multiexp_serial(coeffs: &[Scalar], bases: &[G1Affine], c: usize, acc: &mut G1Projective) {
+    let segments = (Scalar::NUM_BITS as usize / c) + 1;
+
+    for segment in (0..segments).rev() {
+        for _ in 0..c {
+            *acc = acc.double();
+        }
+
+        multiexp_segment(coeffs, bases, c, segment, acc);
+    }
+}
+
+/// `rayon`-parallel version of [`multiexp`]: each `c`-bit segment is
+/// accumulated on its own thread, and the partial sums are combined by
+/// doubling into place from the most-significant segment down.
+#[cfg(feature = "parallel")]
+pub fn multiexp_parallel(coeffs: &[Scalar], bases: &[G1Affine]) -> G1Projective {
+    use rayon::prelude::*;
+
+    assert_eq!(coeffs.len(), bases.len());
+
+    let c = window_size(coeffs.len());
+    let segments = (Scalar::NUM_BITS as usize / c) + 1;
+
+    let mut partials: Vec<G1Projective> = (0..segments)
+        .into_par_iter()
+        .map(|segment| {
+            let mut acc = G1Projective::identity();
+            multiexp_segment(coeffs, bases, c, segment, &mut acc);
+            acc
+        })
+        .collect();
+
+    let mut acc = G1Projective::identity();
+    while let Some(segment_sum) = partials.pop() {
+        for _ in 0..c {
+            acc = acc.double();
+        }
+        acc += segment_sum;
+    }
+    acc
+}
+
+// Picks a `c`-bit window size that roughly minimizes the total number of
+// bucket additions and doublings for `n` terms: too small a window means
+// too many segments (and redundant doublings), too large means too many
+// (mostly empty) buckets per segment.
+fn window_size(n: usize) -> usize {
+    if n < 4 {
+        1
+    } else if n < 32 {
+        3
+    } else {
+        (n as f64).ln().ceil() as usize
+    }
+}
+
+// Accumulates the contribution of a single `c`-bit segment (the
+// `current_segment`-th window of every scalar, counting from the
+// least-significant bit) into `acc`, without the doubling that moves `acc` from one
+// segment's scale to the next -- callers are responsible for that.
+fn multiexp_segment(
+    coeffs: &[Scalar],
+    bases: &[G1Affine],
+    c: usize,
+    current_segment: usize,
+    acc: &mut G1Projective,
+) {
+    let mut buckets = vec![G1Projective::identity(); (1 << c) - 1];
+
+    for (coeff, base) in coeffs.iter().zip(bases.iter()) {
+        let window = window_index(coeff, current_segment, c);
+        if window != 0 {
+            buckets[window - 1] += base;
+        }
+    }
+
+    // Summation by parts: walking the buckets from the top down and
+    // keeping a running sum turns `sum_k k * buckets[k]` into a single
+    // pass of additions instead of one scalar multiplication per bucket.
+    let mut running_sum = G1Projective::identity();
+    for bucket in buckets.into_iter().rev() {
+        running_sum += bucket;
+        *acc += running_sum;
+    }
+}
+
+// Extracts the `current_segment`-th `c`-bit window (counting from the
+// least-significant bit) out of `coeff`'s little-endian byte
+// representation.
+fn window_index(coeff: &Scalar, current_segment: usize, c: usize) -> usize {
+    let skip_bits = current_segment * c;
+    let skip_bytes = skip_bits / 8;
+
+    let repr = coeff.to_repr();
+    let bytes = repr.as_ref();
+
+    if skip_bytes >= bytes.len() {
+        return 0;
+    }
+
+    let mut v = [0u8; 8];
+    for (v, o) in v.iter_mut().zip(bytes[skip_bytes..].iter()) {
+        *v = *o;
+    }
+
+    let mut tmp = u64::from_le_bytes(v);
+    tmp >>= skip_bits - (skip_bytes * 8);
+    tmp &= (1 << c) - 1;
+
+    tmp as usize
+}
+
+#[cfg(test)]
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+#[cfg(test)]
+fn naive_multiexp(coeffs: &[Scalar], bases: &[G1Affine]) -> G1Projective {
+    let mut acc = G1Projective::identity();
+    for (coeff, base) in coeffs.iter().zip(bases.iter()) {
+        acc += G1Projective::from(*base) * coeff;
+    }
+    acc
+}
+
+#[test]
+fn multiexp_matches_naive() {
+    let rng = &mut SmallRng::from_seed([7; 32]);
+
+    for n in [0usize, 1, 2, 5, 16, 100] {
+        let coeffs: Vec<Scalar> = (0..n).map(|_| Scalar::from(rng.gen::<u64>())).collect();
+        let bases: Vec<G1Affine> = (0..n)
+            .map(|_| G1Affine::from(G1Projective::generator() * Scalar::from(rng.gen::<u64>())))
+            .collect();
+
+        assert_eq!(multiexp(&coeffs, &bases), naive_multiexp(&coeffs, &bases));
+    }
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn multiexp_parallel_matches_serial() {
+    let rng = &mut SmallRng::from_seed([8; 32]);
+
+    for n in [0usize, 1, 2, 5, 16, 100] {
+        let coeffs: Vec<Scalar> = (0..n).map(|_| Scalar::from(rng.gen::<u64>())).collect();
+        let bases: Vec<G1Affine> = (0..n)
+            .map(|_| G1Affine::from(G1Projective::generator() * Scalar::from(rng.gen::<u64>())))
+            .collect();
+
+        assert_eq!(multiexp(&coeffs, &bases), multiexp_parallel(&coeffs, &bases));
+    }
+}